@@ -0,0 +1,218 @@
+//! Optional `whatson.toml` configuration discovered in ancestor directories.
+//!
+//! Mirroring how Cargo locates `.cargo/config.toml`, we walk upward from the
+//! current directory looking for a `whatson.toml`. When present it lets a fork
+//! retarget the launcher — build directory, CMake target, extra `cmake`
+//! arguments, and the list of prebuilt executables — without recompiling.
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Default CMake target invoked by `run`.
+pub const DEFAULT_TARGET: &str = "whatson_run_app";
+
+/// Default prebuilt executable locations, relative to the build directory.
+pub const DEFAULT_EXECUTABLES: &[&str] = &[
+    "src/app/bin/WhatSon.app/Contents/MacOS/WhatSon",
+    "src/app/bin/WhatSon",
+    "src/app/WhatSon",
+];
+
+/// Launcher configuration, with compiled-in defaults applied for any key the
+/// user did not override.
+#[derive(Debug, Default)]
+pub struct Config {
+    build_dir: Option<String>,
+    target: Option<String>,
+    configure_args: Vec<String>,
+    build_args: Vec<String>,
+    executables: Vec<String>,
+}
+
+impl Config {
+    /// Searches from the current working directory upward for a `whatson.toml`,
+    /// returning the parsed config or defaults when none is found.
+    pub fn discover() -> Result<Config, String> {
+        let cwd = env::current_dir().map_err(|e| format!("cannot read current directory: {e}"))?;
+        let start = cwd.canonicalize().unwrap_or(cwd);
+        for dir in start.ancestors() {
+            let candidate = dir.join("whatson.toml");
+            if candidate.is_file() {
+                return Config::load(&candidate);
+            }
+        }
+        Ok(Config::default())
+    }
+
+    /// Parses a specific `whatson.toml`.
+    pub fn load(path: &Path) -> Result<Config, String> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+        let config = parse(&contents).map_err(|e| format!("{}: {e}", path.display()))?;
+        Ok(config)
+    }
+
+    /// The build directory relative to `root`, honoring an override.
+    pub fn build_dir(&self, root: &Path) -> PathBuf {
+        root.join(self.build_dir.as_deref().unwrap_or("build"))
+    }
+
+    /// The CMake target name for `run`.
+    pub fn target(&self) -> &str {
+        self.target.as_deref().unwrap_or(DEFAULT_TARGET)
+    }
+
+    /// Extra arguments forwarded to the `cmake` configure step.
+    pub fn configure_args(&self) -> &[String] {
+        &self.configure_args
+    }
+
+    /// Extra arguments forwarded to the `cmake --build` step.
+    pub fn build_args(&self) -> &[String] {
+        &self.build_args
+    }
+
+    /// The ordered prebuilt executable paths to try, relative to the build
+    /// directory (see [`Config::build_dir`]) so that cross builds, which
+    /// build under a target-specific subdirectory, resolve to the right
+    /// binary.
+    pub fn executables(&self) -> Vec<String> {
+        if self.executables.is_empty() {
+            DEFAULT_EXECUTABLES.iter().map(|s| s.to_string()).collect()
+        } else {
+            self.executables.clone()
+        }
+    }
+}
+
+/// Parses the small subset of TOML this launcher needs: top-level string and
+/// array-of-string keys, `#` comments, and blank lines.
+fn parse(contents: &str) -> Result<Config, String> {
+    let mut config = Config::default();
+    for (lineno, raw) in contents.lines().enumerate() {
+        let line = strip_comment(raw).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (key, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected `key = value`", lineno + 1))?;
+        let key = key.trim();
+        let value = value.trim();
+        match key {
+            "build_dir" => config.build_dir = Some(parse_string(value, lineno)?),
+            "target" => config.target = Some(parse_string(value, lineno)?),
+            "configure_args" => config.configure_args = parse_array(value, lineno)?,
+            "build_args" => config.build_args = parse_array(value, lineno)?,
+            "executables" => config.executables = parse_array(value, lineno)?,
+            other => return Err(format!("line {}: unknown key `{other}`", lineno + 1)),
+        }
+    }
+    Ok(config)
+}
+
+fn strip_comment(line: &str) -> &str {
+    // `#` only starts a comment when it is not inside a quoted string.
+    let mut in_string = false;
+    for (i, ch) in line.char_indices() {
+        match ch {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn parse_string(value: &str, lineno: usize) -> Result<String, String> {
+    let trimmed = value.trim();
+    let inner = trimmed
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(|| format!("line {}: expected a quoted string", lineno + 1))?;
+    Ok(inner.to_string())
+}
+
+fn parse_array(value: &str, lineno: usize) -> Result<Vec<String>, String> {
+    let inner = value
+        .trim()
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+        .ok_or_else(|| format!("line {}: expected an array `[...]`", lineno + 1))?;
+    let mut items = Vec::new();
+    for part in inner.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        items.push(parse_string(part, lineno)?);
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_scalar_and_array_keys() {
+        let config = parse(
+            r#"
+            build_dir = "out"
+            target = "custom_target"
+            configure_args = ["-DFOO=1", "-DBAR=2"]
+            executables = ["bin/app"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.build_dir.as_deref(), Some("out"));
+        assert_eq!(config.target(), "custom_target");
+        assert_eq!(config.configure_args(), &["-DFOO=1", "-DBAR=2"]);
+        assert_eq!(config.executables(), vec!["bin/app".to_string()]);
+    }
+
+    #[test]
+    fn applies_defaults_for_missing_keys() {
+        let config = parse("target = \"only_this\"").unwrap();
+        assert_eq!(config.target(), "only_this");
+        assert!(config.configure_args().is_empty());
+        assert_eq!(
+            config.executables(),
+            DEFAULT_EXECUTABLES
+                .iter()
+                .map(|s| s.to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn ignores_comments_and_blank_lines() {
+        let config = parse(
+            "\n# a comment\ntarget = \"t\" # trailing comment\n\nbuild_dir = \"b\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.target(), "t");
+        assert_eq!(config.build_dir.as_deref(), Some("b"));
+    }
+
+    #[test]
+    fn hash_inside_quotes_is_not_a_comment() {
+        let config = parse(r#"target = "t#1""#).unwrap();
+        assert_eq!(config.target(), "t#1");
+    }
+
+    #[test]
+    fn rejects_unknown_key() {
+        assert!(parse("nope = \"x\"").is_err());
+    }
+
+    #[test]
+    fn rejects_unquoted_scalar() {
+        assert!(parse("target = bare").is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_array() {
+        assert!(parse("configure_args = \"not-an-array\"").is_err());
+    }
+}
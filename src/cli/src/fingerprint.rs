@@ -0,0 +1,83 @@
+//! Build-freshness fingerprinting.
+//!
+//! A fingerprint summarises the tracked build inputs — `CMakeLists.txt`,
+//! everything under `src/`, and the resolved CMake arguments — as a single
+//! hash. Comparing it against the value stored after the last successful build
+//! lets warm `run` invocations skip `cmake --build` entirely.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Name of the file holding the last successful build's fingerprint, stored
+/// inside the build directory.
+pub const FINGERPRINT_FILE: &str = ".whatson-fingerprint";
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Computes a fingerprint over the tracked inputs rooted at `root` together
+/// with the `args` that will be passed to `cmake`.
+pub fn compute(root: &Path, args: &[String]) -> io::Result<String> {
+    let mut hash = FNV_OFFSET;
+
+    for arg in args {
+        mix(&mut hash, arg.as_bytes());
+    }
+
+    let mut inputs = vec![root.join("CMakeLists.txt")];
+    collect_files(&root.join("src"), &mut inputs)?;
+    inputs.sort();
+
+    for path in &inputs {
+        mix(&mut hash, path.to_string_lossy().as_bytes());
+        if let Ok(meta) = path.metadata() {
+            mix(&mut hash, &meta.len().to_le_bytes());
+            if let Ok(modified) = meta.modified() {
+                if let Ok(since) = modified.duration_since(UNIX_EPOCH) {
+                    mix(&mut hash, &since.as_nanos().to_le_bytes());
+                }
+            }
+        }
+    }
+
+    Ok(format!("{hash:016x}"))
+}
+
+/// Reads the stored fingerprint, returning `None` if it is missing or unreadable.
+pub fn read(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+/// Writes `fingerprint` to `path`, creating the parent build directory if needed.
+pub fn write(path: &Path, fingerprint: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, fingerprint)
+}
+
+fn collect_files(dir: &Path, out: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(&path, out)?;
+        } else {
+            out.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn mix(hash: &mut u64, bytes: &[u8]) {
+    for &byte in bytes {
+        *hash ^= u64::from(byte);
+        *hash = hash.wrapping_mul(FNV_PRIME);
+    }
+}
@@ -1,77 +1,392 @@
+mod config;
+mod fingerprint;
+mod sanity;
+mod target;
+
 use std::collections::HashSet;
 use std::env;
 use std::path::{Path, PathBuf};
 use std::process::{self, Command};
 
-const APP_EXECUTABLES: &[&str] = &[
-    "build/src/app/bin/WhatSon.app/Contents/MacOS/WhatSon",
-    "build/src/app/bin/WhatSon",
-    "build/src/app/WhatSon",
-];
+use config::Config;
+use target::Target;
+
+const HELP: &str = "\
+WhatSon launcher
+
+Usage: whatson [COMMAND] [-- CMAKE ARGS...]
+
+Commands:
+  run          Launch the prebuilt app, or configure+build+run it (default)
+  configure    Run only the CMake configure step
+  build        Configure if needed, then build (never launches the app)
+  test         Configure and build if needed, then run the test suite via ctest
+  clean        Remove the build directory
+
+Any trailing arguments are forwarded to the underlying cmake/ctest invocation.
+
+Options:
+  --target <TRIPLE>        Cross-compile for TRIPLE (builds in build/<triple>)
+  --toolchain-file <PATH>  Pass PATH as CMAKE_TOOLCHAIN_FILE
+  --force                  Rebuild even if the freshness cache is up to date
+  -h, --help               Show this help
+";
+
+/// The launcher action selected on the command line.
+enum Subcommand {
+    Configure,
+    Build,
+    Run,
+    Test,
+    Clean,
+}
+
+/// Whether `subcommand` shells out to cmake/the compiler/the build tool, and
+/// therefore needs the toolchain preflight to have passed first.
+fn needs_toolchain(subcommand: &Subcommand) -> bool {
+    !matches!(subcommand, Subcommand::Clean)
+}
+
+/// Launcher options parsed after the subcommand, plus any arguments forwarded
+/// verbatim to the underlying `cmake`/`ctest` invocation.
+struct Options {
+    target: Option<Target>,
+    toolchain_file: Option<PathBuf>,
+    force: bool,
+    extra: Vec<String>,
+}
+
+/// Reasons argument parsing does not yield a subcommand.
+enum ArgError {
+    Help,
+    Unknown(String),
+    MissingValue(String),
+}
 
 fn main() {
-    let Some(root) = discover_root() else {
-        eprintln!(
-            "WhatSon root directory was not found. Run from the repository or set WHATSON_ROOT."
-        );
-        process::exit(1);
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (subcommand, options) = match parse_args(&args) {
+        Ok(parsed) => parsed,
+        Err(ArgError::Help) => {
+            print!("{HELP}");
+            process::exit(0);
+        }
+        Err(ArgError::Unknown(cmd)) => {
+            eprintln!("Unknown command `{cmd}`. Run with --help for usage.");
+            process::exit(2);
+        }
+        Err(ArgError::MissingValue(flag)) => {
+            eprintln!("Option `{flag}` requires a value.");
+            process::exit(2);
+        }
     };
 
-    match launch_prebuilt(&root) {
-        Ok(true) => process::exit(0),
-        Ok(false) => {}
-        Err(err) => {
-            eprintln!("Failed to launch prebuilt app: {err}");
+    // `clean` never shells out to the toolchain, so it shouldn't be blocked
+    // by a missing cmake/compiler/build-tool install.
+    if needs_toolchain(&subcommand) {
+        if let Err(missing) = sanity::preflight(sanity::required_tools()) {
+            eprintln!("{missing}");
+            process::exit(1);
         }
     }
 
-    match run_cmake_target(&root) {
+    let config = match Config::discover() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Failed to load whatson.toml: {err}");
+            process::exit(1);
+        }
+    };
+
+    let root = match discover_root() {
+        Ok(root) => root,
+        Err(err) => {
+            eprintln!("{err}");
+            process::exit(1);
+        }
+    };
+
+    let result = match subcommand {
+        Subcommand::Configure => cmd_configure(&root, &config, &options),
+        Subcommand::Build => cmd_build(&root, &config, &options),
+        Subcommand::Run => cmd_run(&root, &config, &options),
+        Subcommand::Test => cmd_test(&root, &config, &options),
+        Subcommand::Clean => cmd_clean(&root, &config, &options),
+    };
+
+    match result {
         Ok(code) => process::exit(code),
         Err(err) => {
-            eprintln!("Failed to run cmake target whatson_run_app: {err}");
+            eprintln!("{err}");
             process::exit(1);
         }
     }
 }
 
-fn discover_root() -> Option<PathBuf> {
-    let mut candidates = Vec::new();
+fn parse_args(args: &[String]) -> Result<(Subcommand, Options), ArgError> {
+    let mut iter = args.iter();
+    let subcommand = match iter.next().map(String::as_str) {
+        None => Subcommand::Run,
+        Some("-h") | Some("--help") => return Err(ArgError::Help),
+        Some("run") => Subcommand::Run,
+        Some("configure") => Subcommand::Configure,
+        Some("build") => Subcommand::Build,
+        Some("test") => Subcommand::Test,
+        Some("clean") => Subcommand::Clean,
+        Some(other) => return Err(ArgError::Unknown(other.to_string())),
+    };
+
+    let mut options = Options {
+        target: None,
+        toolchain_file: None,
+        force: false,
+        extra: Vec::new(),
+    };
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Err(ArgError::Help),
+            "--" => {
+                options.extra.extend(iter.by_ref().cloned());
+                break;
+            }
+            "--force" => options.force = true,
+            "--target" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| ArgError::MissingValue("--target".to_string()))?;
+                options.target = Some(Target::new(value.clone()));
+            }
+            "--toolchain-file" => {
+                let value = iter
+                    .next()
+                    .ok_or_else(|| ArgError::MissingValue("--toolchain-file".to_string()))?;
+                options.toolchain_file = Some(PathBuf::from(value));
+            }
+            _ => options.extra.push(arg.clone()),
+        }
+    }
 
-    if let Ok(root) = env::var("WHATSON_ROOT") {
-        candidates.push(PathBuf::from(root));
+    Ok((subcommand, options))
+}
+
+/// The build directory for this invocation: `build/<triple>` for a cross
+/// build, the configured base directory otherwise.
+fn build_dir_for(root: &Path, config: &Config, options: &Options) -> PathBuf {
+    let base = config.build_dir(root);
+    match &options.target {
+        Some(target) if !target.is_host() => base.join(target.triple()),
+        _ => base,
+    }
+}
+
+fn cmd_configure(root: &Path, config: &Config, options: &Options) -> Result<i32, std::io::Error> {
+    let build_dir = build_dir_for(root, config, options);
+    configure(root, config, &build_dir, options, &options.extra)
+}
+
+fn cmd_build(root: &Path, config: &Config, options: &Options) -> Result<i32, std::io::Error> {
+    // `build` only ever compiles — it must never launch the app, regardless
+    // of what CMake target `run` happens to be configured to use.
+    ensure_built(root, config, options, None, false)
+}
+
+fn cmd_run(root: &Path, config: &Config, options: &Options) -> Result<i32, std::io::Error> {
+    // A foreign binary can't be launched here, so never take the launch path.
+    let runnable = options
+        .target
+        .as_ref()
+        .map(Target::is_host)
+        .unwrap_or(true);
+    ensure_built(root, config, options, Some(config.target()), runnable)
+}
+
+/// Configures if needed, then builds `build_target` (the default CMake
+/// target when `None`) — unless the freshness cache shows nothing changed
+/// and the executable already exists, in which case the build is skipped.
+/// When `launch` is set, the prebuilt binary is launched once it is known to
+/// be up to date, whether that is because the cache was already fresh or
+/// because the build that just ran produced it.
+fn ensure_built(
+    root: &Path,
+    config: &Config,
+    options: &Options,
+    build_target: Option<&str>,
+    launch: bool,
+) -> Result<i32, std::io::Error> {
+    let build_dir = build_dir_for(root, config, options);
+    if !build_dir.exists() {
+        let code = configure(root, config, &build_dir, options, &[])?;
+        if code != 0 {
+            return Ok(code);
+        }
     }
 
+    let resolved = resolved_cmake_args(config, options);
+    let current = fingerprint::compute(root, &resolved)?;
+    let fingerprint_path = build_dir.join(fingerprint::FINGERPRINT_FILE);
+    let executable_present = config
+        .executables()
+        .iter()
+        .any(|rel| build_dir.join(rel).is_file());
+
+    let fresh = !options.force
+        && executable_present
+        && fingerprint::read(&fingerprint_path).as_deref() == Some(current.as_str());
+    if fresh {
+        if launch {
+            return launch_prebuilt_result(&build_dir, config);
+        }
+        return Ok(0);
+    }
+
+    let code = build(config, &build_dir, build_target, &options.extra)?;
+    if code != 0 {
+        return Ok(code);
+    }
+    fingerprint::write(&fingerprint_path, &current)?;
+    if launch {
+        return launch_prebuilt_result(&build_dir, config);
+    }
+    Ok(code)
+}
+
+/// Runs [`launch_prebuilt`] and maps its outcome to a process exit code.
+fn launch_prebuilt_result(build_dir: &Path, config: &Config) -> Result<i32, std::io::Error> {
+    match launch_prebuilt(build_dir, config) {
+        Ok(true) => Ok(0),
+        Ok(false) => Ok(1),
+        Err(err) => {
+            eprintln!("Failed to launch prebuilt app: {err}");
+            Ok(1)
+        }
+    }
+}
+
+/// The CMake arguments that influence build output, for fingerprinting.
+///
+/// Note: a mismatch only forces `cmake --build` to run again, not
+/// `cmake -S -B`, so a changed `configure_args`/`--toolchain-file` has no
+/// effect on an existing build directory until it is `clean`ed — the CMake
+/// cache itself is untouched.
+fn resolved_cmake_args(config: &Config, options: &Options) -> Vec<String> {
+    let mut args: Vec<String> = Vec::new();
+    args.push(config.target().to_string());
+    args.extend(config.configure_args().iter().cloned());
+    args.extend(config.build_args().iter().cloned());
+    if let Some(toolchain) = &options.toolchain_file {
+        args.push(toolchain.display().to_string());
+    }
+    if let Some(target) = &options.target {
+        args.push(target.triple().to_string());
+    }
+    args.extend(options.extra.iter().cloned());
+    args
+}
+
+fn cmd_test(root: &Path, config: &Config, options: &Options) -> Result<i32, std::io::Error> {
+    let build_dir = build_dir_for(root, config, options);
+    if !build_dir.exists() {
+        let code = configure(root, config, &build_dir, options, &[])?;
+        if code != 0 {
+            return Ok(code);
+        }
+    }
+    let code = build(config, &build_dir, None, &[])?;
+    if code != 0 {
+        return Ok(code);
+    }
+    let status = Command::new("ctest")
+        .arg("--test-dir")
+        .arg(&build_dir)
+        .arg("--output-on-failure")
+        .args(&options.extra)
+        .status()?;
+    Ok(status.code().unwrap_or(1))
+}
+
+fn cmd_clean(root: &Path, config: &Config, options: &Options) -> Result<i32, std::io::Error> {
+    let build_dir = build_dir_for(root, config, options);
+    if build_dir.exists() {
+        std::fs::remove_dir_all(&build_dir)?;
+    }
+    Ok(0)
+}
+
+/// Resolves the WhatSon project root with explicit, ordered precedence:
+/// an explicit `WHATSON_ROOT` must be valid or we fail loudly, otherwise we
+/// walk the cwd ancestors (and the compiled-in manifest parent) for the first
+/// directory that is a project root.
+fn discover_root() -> Result<PathBuf, String> {
+    if let Some(root) = env::var_os("WHATSON_ROOT") {
+        let raw = PathBuf::from(&root);
+        let canonical = raw.canonicalize().map_err(|err| {
+            format!(
+                "WHATSON_ROOT is set to `{}`, which could not be resolved: {err}",
+                raw.display()
+            )
+        })?;
+        let missing = missing_markers(&canonical);
+        if missing.is_empty() {
+            return Ok(canonical);
+        }
+        return Err(format!(
+            "WHATSON_ROOT points at `{}`, which is not a WhatSon project root (missing {}).",
+            canonical.display(),
+            missing.join(", ")
+        ));
+    }
+
+    let mut candidates = Vec::new();
     if let Ok(cwd) = env::current_dir() {
         candidates.extend(cwd.ancestors().map(PathBuf::from));
     }
-
-    let manifest_parent = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..");
-    candidates.push(
-        manifest_parent
-            .canonicalize()
-            .unwrap_or_else(|_| manifest_parent.clone()),
-    );
+    candidates.push(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../.."));
 
     let mut seen = HashSet::new();
-    for path in candidates {
-        if !seen.insert(path.clone()) {
+    let mut near_misses = Vec::new();
+    for candidate in candidates {
+        // Canonicalize before dedup so symlinked paths collapse to one attempt.
+        let Ok(canonical) = candidate.canonicalize() else {
             continue;
+        };
+        if !seen.insert(canonical.clone()) {
+            continue;
+        }
+        if missing_markers(&canonical).is_empty() {
+            return Ok(canonical);
         }
-        if is_project_root(&path) {
-            return Some(path);
+        if canonical.join("CMakeLists.txt").is_file() {
+            near_misses.push(canonical);
         }
     }
 
-    None
+    let mut message = String::from(
+        "WhatSon root directory was not found. Run from the repository or set WHATSON_ROOT.",
+    );
+    if !near_misses.is_empty() {
+        message.push_str("\nNearby directories have a CMakeLists.txt but no src/app/main.cpp:");
+        for dir in near_misses {
+            message.push_str(&format!("\n  {}", dir.display()));
+        }
+    }
+    Err(message)
 }
 
-fn is_project_root(path: &Path) -> bool {
-    path.join("CMakeLists.txt").is_file() && path.join("src/app/main.cpp").is_file()
+/// The project-root marker files absent from `path`, in discovery order.
+fn missing_markers(path: &Path) -> Vec<String> {
+    let mut missing = Vec::new();
+    if !path.join("CMakeLists.txt").is_file() {
+        missing.push("CMakeLists.txt".to_string());
+    }
+    if !path.join("src/app/main.cpp").is_file() {
+        missing.push("src/app/main.cpp".to_string());
+    }
+    missing
 }
 
-fn launch_prebuilt(root: &Path) -> Result<bool, std::io::Error> {
-    for rel in APP_EXECUTABLES {
-        let executable = root.join(rel);
+fn launch_prebuilt(build_dir: &Path, config: &Config) -> Result<bool, std::io::Error> {
+    for rel in config.executables() {
+        let executable = build_dir.join(&rel);
         if executable.is_file() {
             let status = Command::new(&executable).status()?;
             return Ok(status.success());
@@ -80,26 +395,50 @@ fn launch_prebuilt(root: &Path) -> Result<bool, std::io::Error> {
     Ok(false)
 }
 
-fn run_cmake_target(root: &Path) -> Result<i32, std::io::Error> {
-    let build_dir = root.join("build");
-    if !build_dir.exists() {
-        let configure_status = Command::new("cmake")
-            .arg("-S")
-            .arg(root)
-            .arg("-B")
-            .arg(&build_dir)
-            .status()?;
-        if !configure_status.success() {
-            return Ok(configure_status.code().unwrap_or(1));
+fn configure(
+    root: &Path,
+    config: &Config,
+    build_dir: &Path,
+    options: &Options,
+    extra: &[String],
+) -> Result<i32, std::io::Error> {
+    let mut command = Command::new("cmake");
+    command
+        .arg("-S")
+        .arg(root)
+        .arg("-B")
+        .arg(build_dir)
+        .args(config.configure_args());
+
+    if let Some(toolchain) = &options.toolchain_file {
+        command.arg(format!("-DCMAKE_TOOLCHAIN_FILE={}", toolchain.display()));
+    }
+
+    if let Some(target) = &options.target {
+        if !target.is_host() {
+            command.arg(format!("-DCMAKE_SYSTEM_NAME={}", target.cmake_system_name()));
+            command.arg(format!(
+                "-DCMAKE_SYSTEM_PROCESSOR={}",
+                target.cmake_system_processor()
+            ));
         }
     }
 
-    let build_status = Command::new("cmake")
-        .arg("--build")
-        .arg(&build_dir)
-        .arg("--target")
-        .arg("whatson_run_app")
-        .status()?;
+    let status = command.args(extra).status()?;
+    Ok(status.code().unwrap_or(1))
+}
 
-    Ok(build_status.code().unwrap_or(1))
+fn build(
+    config: &Config,
+    build_dir: &Path,
+    target: Option<&str>,
+    extra: &[String],
+) -> Result<i32, std::io::Error> {
+    let mut command = Command::new("cmake");
+    command.arg("--build").arg(build_dir);
+    if let Some(target) = target {
+        command.arg("--target").arg(target);
+    }
+    let status = command.args(config.build_args()).args(extra).status()?;
+    Ok(status.code().unwrap_or(1))
 }
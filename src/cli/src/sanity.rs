@@ -0,0 +1,191 @@
+//! Preflight checks for the external toolchain the launcher shells out to.
+//!
+//! Without this, a missing `cmake` or compiler only surfaces as an opaque I/O
+//! error once we are already mid-build. Checking up front lets us report every
+//! missing tool, and the directories we searched, in a single message.
+
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Locates executables on the `PATH`, caching each lookup so repeated queries
+/// for the same program are free.
+pub struct Finder {
+    dirs: Vec<PathBuf>,
+    extensions: Vec<OsString>,
+    cache: HashMap<OsString, Option<PathBuf>>,
+}
+
+impl Finder {
+    /// Builds a finder seeded from the current `PATH` and platform executable
+    /// extensions.
+    pub fn new() -> Self {
+        let dirs = env::var_os("PATH")
+            .map(|path| env::split_paths(&path).collect())
+            .unwrap_or_default();
+        Finder {
+            dirs,
+            extensions: executable_extensions(),
+            cache: HashMap::new(),
+        }
+    }
+
+    /// Resolves `program` against the `PATH`, returning its full path or `None`
+    /// if it is not found. Results are memoised per program name.
+    pub fn find(&mut self, program: &str) -> Option<PathBuf> {
+        let key = OsString::from(program);
+        if let Some(hit) = self.cache.get(&key) {
+            return hit.clone();
+        }
+        let resolved = self.search(program);
+        self.cache.insert(key, resolved.clone());
+        resolved
+    }
+
+    fn search(&self, program: &str) -> Option<PathBuf> {
+        for dir in &self.dirs {
+            let bare = dir.join(program);
+            if is_executable(&bare) {
+                return Some(bare);
+            }
+            for ext in &self.extensions {
+                let mut name = OsString::from(program);
+                name.push(ext);
+                let candidate = dir.join(&name);
+                if is_executable(&candidate) {
+                    return Some(candidate);
+                }
+            }
+        }
+        None
+    }
+
+    /// The directories that were searched, for inclusion in diagnostics.
+    pub fn search_dirs(&self) -> &[PathBuf] {
+        &self.dirs
+    }
+}
+
+impl Default for Finder {
+    fn default() -> Self {
+        Finder::new()
+    }
+}
+
+/// A single tool requirement, satisfied by any one of its candidate names
+/// (e.g. `make` *or* `ninja`).
+pub struct Requirement {
+    /// What the tool is needed for, shown in diagnostics.
+    pub purpose: &'static str,
+    /// Interchangeable program names; finding any one satisfies the requirement.
+    pub candidates: &'static [&'static str],
+}
+
+/// The toolchain the launcher depends on before it can configure or build.
+pub fn required_tools() -> &'static [Requirement] {
+    &[
+        Requirement {
+            purpose: "CMake build driver",
+            candidates: &["cmake"],
+        },
+        Requirement {
+            purpose: "C++ compiler",
+            candidates: &["c++", "clang++", "g++"],
+        },
+        Requirement {
+            purpose: "build tool",
+            candidates: &["ninja", "make"],
+        },
+    ]
+}
+
+/// Checks that every requirement is satisfiable on the current `PATH`.
+pub fn preflight(requirements: &[Requirement]) -> Result<(), MissingTools> {
+    let mut finder = Finder::new();
+    let mut missing = Vec::new();
+    for req in requirements {
+        if !req
+            .candidates
+            .iter()
+            .any(|name| finder.find(name).is_some())
+        {
+            missing.push(format!(
+                "{} (looked for {})",
+                req.purpose,
+                req.candidates.join(", ")
+            ));
+        }
+    }
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(MissingTools {
+            missing,
+            searched: finder.search_dirs().to_vec(),
+        })
+    }
+}
+
+/// Aggregated report of every tool that could not be located.
+pub struct MissingTools {
+    missing: Vec<String>,
+    searched: Vec<PathBuf>,
+}
+
+impl fmt::Display for MissingTools {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "required tools were not found on PATH:")?;
+        for tool in &self.missing {
+            writeln!(f, "  - {tool}")?;
+        }
+        if self.searched.is_empty() {
+            write!(f, "searched directories: <PATH is empty or unset>")
+        } else {
+            writeln!(f, "searched directories:")?;
+            for (i, dir) in self.searched.iter().enumerate() {
+                let last = i + 1 == self.searched.len();
+                if last {
+                    write!(f, "  {}", dir.display())?;
+                } else {
+                    writeln!(f, "  {}", dir.display())?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(windows)]
+fn executable_extensions() -> Vec<OsString> {
+    let mut extensions = vec![OsString::from(".exe")];
+    if let Some(pathext) = env::var_os("PATHEXT") {
+        for ext in env::split_paths(&pathext) {
+            let ext = ext.into_os_string();
+            if !ext.is_empty() && !extensions.iter().any(|e| e.eq_ignore_ascii_case(&ext)) {
+                extensions.push(ext);
+            }
+        }
+    }
+    extensions
+}
+
+#[cfg(not(windows))]
+fn executable_extensions() -> Vec<OsString> {
+    Vec::new()
+}
+
+#[cfg(not(windows))]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    match std::fs::metadata(path) {
+        Ok(meta) => meta.is_file() && meta.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
+
+#[cfg(windows)]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
@@ -0,0 +1,153 @@
+//! Target-triple handling for cross builds.
+//!
+//! A `--target` triple is translated into the `CMAKE_SYSTEM_NAME` /
+//! `CMAKE_SYSTEM_PROCESSOR` pair CMake expects, and compared against the host
+//! so we know when a produced binary cannot be run locally.
+
+/// A parsed target triple such as `aarch64-apple-darwin`.
+pub struct Target {
+    triple: String,
+}
+
+impl Target {
+    /// Wraps a triple string verbatim.
+    pub fn new(triple: impl Into<String>) -> Self {
+        Target {
+            triple: triple.into(),
+        }
+    }
+
+    /// The original triple string.
+    pub fn triple(&self) -> &str {
+        &self.triple
+    }
+
+    /// Whether this triple refers to the machine we are running on, in which
+    /// case no cross configuration is needed and prebuilt binaries are runnable.
+    ///
+    /// Compares the triple's parsed architecture and OS components against
+    /// the host, rather than substring-matching the whole triple: a naive
+    /// `contains` check would mistake e.g. `x86_64-linux-android` for the
+    /// host on an x86_64 Linux machine, even though an Android binary can't
+    /// run there.
+    pub fn is_host(&self) -> bool {
+        let arch = self.triple.split('-').next().unwrap_or(&self.triple);
+        if arch != std::env::consts::ARCH {
+            return false;
+        }
+        let host_os = match std::env::consts::OS {
+            "macos" => "darwin",
+            other => other,
+        };
+        self.system_component() == host_os
+    }
+
+    /// The `CMAKE_SYSTEM_NAME` derived from the triple's OS component.
+    pub fn cmake_system_name(&self) -> String {
+        let sys = self.system_component();
+        match sys {
+            "darwin" | "macos" => "Darwin".to_string(),
+            "ios" => "iOS".to_string(),
+            "linux" => "Linux".to_string(),
+            "windows" => "Windows".to_string(),
+            "android" => "Android".to_string(),
+            "freebsd" => "FreeBSD".to_string(),
+            "none" | "" => "Generic".to_string(),
+            other => capitalize(other),
+        }
+    }
+
+    /// The `CMAKE_SYSTEM_PROCESSOR`, taken from the triple's architecture.
+    pub fn cmake_system_processor(&self) -> String {
+        self.triple
+            .split('-')
+            .next()
+            .unwrap_or(&self.triple)
+            .to_string()
+    }
+
+    fn system_component(&self) -> &str {
+        let parts: Vec<&str> = self.triple.split('-').collect();
+        // Bare-metal triples (e.g. `thumbv7em-none-eabihf`) are
+        // `arch-none-abi` with no vendor slot; special-case them before the
+        // positional `arch-vendor-sys[-abi]` / `arch-sys` heuristic below,
+        // which would otherwise read the abi suffix as the system.
+        if parts.get(1) == Some(&"none") {
+            return "none";
+        }
+        match parts.get(2) {
+            Some(sys) => sys,
+            None => parts.get(1).copied().unwrap_or(""),
+        }
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn host_os_token() -> &'static str {
+        match std::env::consts::OS {
+            "macos" => "darwin",
+            other => other,
+        }
+    }
+
+    #[test]
+    fn is_host_matches_the_current_machine() {
+        let triple = format!("{}-unknown-{}", std::env::consts::ARCH, host_os_token());
+        assert!(Target::new(triple).is_host());
+    }
+
+    #[test]
+    fn is_host_rejects_foreign_os_even_with_host_arch_substring() {
+        // A naive substring match would mistake this for the host whenever
+        // the host is x86_64 Linux, since "linux" appears in the triple.
+        let triple = format!("{}-linux-android", std::env::consts::ARCH);
+        assert!(!Target::new(triple).is_host());
+    }
+
+    #[test]
+    fn is_host_rejects_foreign_arch() {
+        let triple = format!("bogusarch-unknown-{}", host_os_token());
+        assert!(!Target::new(triple).is_host());
+    }
+
+    #[test]
+    fn system_component_reads_three_token_triple() {
+        assert_eq!(Target::new("aarch64-apple-darwin").system_component(), "darwin");
+    }
+
+    #[test]
+    fn system_component_reads_four_token_triple() {
+        assert_eq!(
+            Target::new("x86_64-pc-windows-gnu").system_component(),
+            "windows"
+        );
+    }
+
+    #[test]
+    fn system_component_special_cases_bare_metal_none() {
+        assert_eq!(
+            Target::new("thumbv7em-none-eabihf").system_component(),
+            "none"
+        );
+        assert_eq!(
+            Target::new("thumbv7m-none-eabi").cmake_system_name(),
+            "Generic"
+        );
+    }
+
+    #[test]
+    fn system_component_falls_back_for_vendorless_triple() {
+        assert_eq!(Target::new("arch-sys").system_component(), "sys");
+    }
+}